@@ -0,0 +1,270 @@
+/// GEC: a mutually-authenticated Curve25519 + Ed25519 + HKDF-SHA512 key-exchange handshake.
+///
+/// The handshake runs as an `Initiator`/`Responder` state machine:
+///   1. Initiator sends its ephemeral Curve25519 public key (message 1).
+///   2. Responder generates its own ephemeral keypair, computes z = dh(responder ephemeral
+///      secret, initiator ephemeral public), derives
+///      traffic keys, signs `their_ephemeral_public || our_ephemeral_public`, and sends back
+///      its ephemeral public key and signature (message 2).
+///   3. Initiator verifies the responder's signature, derives the same traffic keys from z,
+///      signs `their_ephemeral_public || our_ephemeral_public`, and sends its signature
+///      (message 3).
+///   4. Responder verifies the initiator's signature.
+use crate::kdf;
+use crate::SecretKey;
+
+/// The three key/salt pairs derived from the shared secret `z`, matching the
+/// `kdf(z, 0)` / `kdf(z, 1)` / `kdf(z, 2)` roles: A's keys, B's keys, and the keys returned
+/// to the caller. Each field is a zeroizing `SecretKey` since these are the session's actual
+/// traffic keys.
+pub struct TrafficKeys {
+    pub k_a: SecretKey,
+    pub s_a: SecretKey,
+    pub k_b: SecretKey,
+    pub s_b: SecretKey,
+    pub k_client: SecretKey,
+    pub s_client: SecretKey,
+}
+
+/// kdf(z, party_ident) via HKDF-SHA512: empty salt, `[party_ident]` as info, 64 bytes of output.
+fn gec_kdf(z: &SecretKey, party_ident: u8) -> Result<[u8; 64], String> {
+    let prk = kdf::hkdf_extract(&[], z.as_bytes());
+    let okm = kdf::hkdf_expand(&prk, &[party_ident], 64)?;
+
+    let mut out = [0u8; 64];
+    out.copy_from_slice(&okm);
+    return Ok(out);
+}
+
+fn derive_traffic_keys(z: &SecretKey) -> Result<TrafficKeys, String> {
+    let ka_sa = gec_kdf(z, 0)?;
+    let kb_sb = gec_kdf(z, 1)?;
+    let kc_sc = gec_kdf(z, 2)?;
+
+    return Ok(TrafficKeys {
+        k_a: SecretKey::new(&ka_sa[0..32])?,
+        s_a: SecretKey::new(&ka_sa[32..64])?,
+        k_b: SecretKey::new(&kb_sb[0..32])?,
+        s_b: SecretKey::new(&kb_sb[32..64])?,
+        k_client: SecretKey::new(&kc_sc[0..32])?,
+        s_client: SecretKey::new(&kc_sc[32..64])?,
+    });
+}
+
+fn curve25519_basepoint() -> [u8; 32] {
+    let mut basepoint = [0u8; 32];
+    basepoint[0] = 9;
+    return basepoint;
+}
+
+/// The signed message is always `their_ephemeral_public || our_ephemeral_public`, from the
+/// signer's point of view; the verifier reconstructs the identical bytes as
+/// `own_ephemeral_public || their_ephemeral_public`.
+fn ephemeral_transcript(first: &[u8], second: &[u8]) -> Vec<u8> {
+    let mut transcript = first.to_vec();
+    transcript.extend_from_slice(second);
+    return transcript;
+}
+
+struct EphemeralKeypair {
+    secret: SecretKey,
+    public: [u8; 32],
+}
+
+fn generate_ephemeral_keypair(mut random_bytes: [u8; 32]) -> Result<EphemeralKeypair, String> {
+    crate::noise::curve25519_clamp(&mut random_bytes);
+    let secret = SecretKey::new(&random_bytes)?;
+
+    let mut public = [0u8; 32];
+    crate::curve25519_crypto_scalarmult(&mut public, &secret, &curve25519_basepoint())?;
+
+    return Ok(EphemeralKeypair { secret, public });
+}
+
+/// Initiator side of a GEC handshake (party A).
+pub struct Initiator {
+    ephemeral: EphemeralKeypair,
+    identity_secret: SecretKey,
+}
+
+impl Initiator {
+    /// `ephemeral_random`: 32 fresh random bytes for the ephemeral Curve25519 keypair.
+    /// `identity_secret`: our long-term Ed25519 signing key.
+    pub fn new(ephemeral_random: [u8; 32], identity_secret: SecretKey) -> Result<Initiator, String> {
+        return Ok(Initiator {
+            ephemeral: generate_ephemeral_keypair(ephemeral_random)?,
+            identity_secret,
+        });
+    }
+
+    /// Message 1: our ephemeral Curve25519 public key.
+    pub fn hello(&self) -> Vec<u8> {
+        return self.ephemeral.public.to_vec();
+    }
+
+    /// Consumes message 2 (the responder's ephemeral public key and signature), verifies it,
+    /// derives the traffic keys, and returns them along with message 3 (our signature).
+    pub fn finish(&self,
+                 their_ephemeral_public: &[u8],
+                 their_identity_public: &[u8],
+                 their_signature: &[u8])
+                 -> Result<(TrafficKeys, Vec<u8>), String> {
+        if their_ephemeral_public.len() != 32 {
+            return Err(String::from("Responder ephemeral public key length error"));
+        }
+
+        let verify_transcript = ephemeral_transcript(&self.ephemeral.public, their_ephemeral_public);
+        let valid = crate::ed25519_verify(their_identity_public, &verify_transcript, their_signature)?;
+        if !valid {
+            return Err(String::from("GEC handshake: responder signature verification failed"));
+        }
+
+        let mut z_bytes = [0u8; 32];
+        crate::curve25519_crypto_scalarmult(&mut z_bytes, &self.ephemeral.secret, their_ephemeral_public)?;
+        let z = SecretKey::new(&z_bytes)?;
+        let keys = derive_traffic_keys(&z)?;
+
+        let sign_transcript = ephemeral_transcript(their_ephemeral_public, &self.ephemeral.public);
+        let mut signature = vec![0u8; 64];
+        crate::ed25519_sign(&mut signature, &self.identity_secret, &sign_transcript)?;
+
+        return Ok((keys, signature));
+    }
+}
+
+/// Responder side of a GEC handshake (party B).
+pub struct Responder {
+    ephemeral: EphemeralKeypair,
+    identity_secret: SecretKey,
+}
+
+impl Responder {
+    /// `ephemeral_random`: 32 fresh random bytes for the ephemeral Curve25519 keypair.
+    /// `identity_secret`: our long-term Ed25519 signing key.
+    pub fn new(ephemeral_random: [u8; 32], identity_secret: SecretKey) -> Result<Responder, String> {
+        return Ok(Responder {
+            ephemeral: generate_ephemeral_keypair(ephemeral_random)?,
+            identity_secret,
+        });
+    }
+
+    /// Consumes message 1 (the initiator's ephemeral public key), derives the traffic keys,
+    /// and returns them along with message 2 (our ephemeral public key and signature).
+    pub fn receive_hello(&self,
+                        their_ephemeral_public: &[u8])
+                        -> Result<(TrafficKeys, Vec<u8>, Vec<u8>), String> {
+        if their_ephemeral_public.len() != 32 {
+            return Err(String::from("Initiator ephemeral public key length error"));
+        }
+
+        let mut z_bytes = [0u8; 32];
+        crate::curve25519_crypto_scalarmult(&mut z_bytes, &self.ephemeral.secret, their_ephemeral_public)?;
+        let z = SecretKey::new(&z_bytes)?;
+        let keys = derive_traffic_keys(&z)?;
+
+        let sign_transcript = ephemeral_transcript(their_ephemeral_public, &self.ephemeral.public);
+        let mut signature = vec![0u8; 64];
+        crate::ed25519_sign(&mut signature, &self.identity_secret, &sign_transcript)?;
+
+        return Ok((keys, self.ephemeral.public.to_vec(), signature));
+    }
+
+    /// Verifies message 3 (the initiator's signature), completing mutual authentication.
+    pub fn verify_finish(&self,
+                         their_ephemeral_public: &[u8],
+                         their_identity_public: &[u8],
+                         their_signature: &[u8])
+                         -> Result<(), String> {
+        if their_ephemeral_public.len() != 32 {
+            return Err(String::from("Initiator ephemeral public key length error"));
+        }
+
+        let verify_transcript = ephemeral_transcript(&self.ephemeral.public, their_ephemeral_public);
+        let valid = crate::ed25519_verify(their_identity_public, &verify_transcript, their_signature)?;
+        if !valid {
+            return Err(String::from("GEC handshake: initiator signature verification failed"));
+        }
+
+        return Ok(());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn identity_keypair(seed: u8) -> (SecretKey, Vec<u8>) {
+        let secret = SecretKey::new(&[seed; 32]).unwrap();
+        let mut public = vec![0; 32];
+        crate::ed25519_secret_to_public(&mut public, &secret).unwrap();
+        return (secret, public);
+    }
+
+    #[test]
+    fn test_gec_full_handshake_agrees_on_keys() {
+        let (initiator_identity_secret, initiator_identity_public) = identity_keypair(0x01);
+        let (responder_identity_secret, responder_identity_public) = identity_keypair(0x02);
+
+        let initiator = Initiator::new([0x11; 32], initiator_identity_secret).expect("initiator");
+        let responder = Responder::new([0x22; 32], responder_identity_secret).expect("responder");
+
+        let message1 = initiator.hello();
+
+        let (responder_keys, message2_ephemeral, message2_signature) =
+            responder.receive_hello(&message1).expect("receive_hello");
+
+        let (initiator_keys, message3_signature) = initiator
+            .finish(&message2_ephemeral, &responder_identity_public, &message2_signature)
+            .expect("finish");
+
+        responder
+            .verify_finish(&message1, &initiator_identity_public, &message3_signature)
+            .expect("verify_finish");
+
+        assert_eq!(initiator_keys.k_a.as_bytes(), responder_keys.k_a.as_bytes());
+        assert_eq!(initiator_keys.s_a.as_bytes(), responder_keys.s_a.as_bytes());
+        assert_eq!(initiator_keys.k_b.as_bytes(), responder_keys.k_b.as_bytes());
+        assert_eq!(initiator_keys.s_b.as_bytes(), responder_keys.s_b.as_bytes());
+        assert_eq!(initiator_keys.k_client.as_bytes(), responder_keys.k_client.as_bytes());
+        assert_eq!(initiator_keys.s_client.as_bytes(), responder_keys.s_client.as_bytes());
+    }
+
+    #[test]
+    fn test_gec_rejects_wrong_responder_signature() {
+        let (initiator_identity_secret, _) = identity_keypair(0x01);
+        let (responder_identity_secret, _) = identity_keypair(0x02);
+        let (_, wrong_identity_public) = identity_keypair(0x03);
+
+        let initiator = Initiator::new([0x11; 32], initiator_identity_secret).expect("initiator");
+        let responder = Responder::new([0x22; 32], responder_identity_secret).expect("responder");
+
+        let message1 = initiator.hello();
+        let (_, message2_ephemeral, message2_signature) =
+            responder.receive_hello(&message1).expect("receive_hello");
+
+        let result = initiator.finish(&message2_ephemeral, &wrong_identity_public, &message2_signature);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_gec_rejects_wrong_initiator_signature() {
+        let (initiator_identity_secret, initiator_identity_public) = identity_keypair(0x01);
+        let (responder_identity_secret, responder_identity_public) = identity_keypair(0x02);
+
+        let initiator = Initiator::new([0x11; 32], initiator_identity_secret).expect("initiator");
+        let responder = Responder::new([0x22; 32], responder_identity_secret).expect("responder");
+
+        let message1 = initiator.hello();
+        let (_, message2_ephemeral, message2_signature) =
+            responder.receive_hello(&message1).expect("receive_hello");
+        let (_, message3_signature) = initiator
+            .finish(&message2_ephemeral, &responder_identity_public, &message2_signature)
+            .expect("finish");
+
+        let mut tampered_signature = message3_signature.clone();
+        tampered_signature[0] ^= 0xff;
+
+        let result = responder.verify_finish(&message1, &initiator_identity_public, &tampered_signature);
+        assert!(result.is_err());
+    }
+}
@@ -14,6 +14,56 @@ const HASH_LEN: usize = 64;
 
 use std::ptr;
 
+pub mod gec;
+pub mod kdf;
+pub mod noise;
+
+/// A 32-byte Curve25519/Ed25519 secret key that zeroes its memory on drop. Neither `Copy` nor
+/// `Clone`/`Debug` so it can't be duplicated or accidentally logged.
+pub struct SecretKey([u8; KEY_LEN]);
+
+impl SecretKey {
+    /// Builds a `SecretKey` from exactly `KEY_LEN` bytes.
+    pub fn new(bytes: &[u8]) -> Result<SecretKey, String> {
+        if bytes.len() != KEY_LEN {
+            return Err(String::from("Secret key length error"));
+        }
+
+        let mut buf = [0u8; KEY_LEN];
+        buf.copy_from_slice(bytes);
+        return Ok(SecretKey(buf));
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        return &self.0;
+    }
+}
+
+impl Drop for SecretKey {
+    fn drop(&mut self) {
+        for byte in self.0.iter_mut() {
+            unsafe {
+                ptr::write_volatile(byte, 0);
+            }
+        }
+    }
+}
+
+/// Compares `a` and `b` in constant time by OR-accumulating byte XORs, so MAC/tag and key
+/// comparisons don't leak timing information through an early exit.
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+
+    let mut diff: u8 = 0;
+    for i in 0..a.len() {
+        diff |= a[i] ^ b[i];
+    }
+
+    return diff == 0;
+}
+
 #[link(name = "hacl")]
 extern "C" {
     fn Chacha20Poly1305_aead_encrypt(c: *const u8,
@@ -54,6 +104,18 @@ extern "C" {
 }
 
 
+#[link(name = "hacl")]
+extern "C" {
+    fn Ed25519_verify(public_key: *const u8, msg: *const u8, len: u32, signature: *const u8) -> bool;
+}
+
+
+#[link(name = "hacl")]
+extern "C" {
+    fn Ed25519_secret_to_public(public_key: *const u8, secret_key: *const u8);
+}
+
+
 
 #[link(name = "hacl")]
 extern "C" {
@@ -69,14 +131,15 @@ pub fn sha2_512_hash(hash: &mut [u8], input: &[u8]) -> Result<(), String> {
         return Err(String::from("Hash length error"));
     }
 
-    if input.is_empty() {
-        return Err(String::from("Can't use an empty input message"));
-    }
-
     let input_len = input.len() as u32;
+    let input_ptr = if input.is_empty() {
+        ptr::null()
+    } else {
+        input.as_ptr()
+    };
 
     unsafe {
-        SHA2_512_hash(hash.as_ptr(), input.as_ptr(), input_len);
+        SHA2_512_hash(hash.as_ptr(), input_ptr, input_len);
     }
 
     return Ok(());
@@ -85,13 +148,10 @@ pub fn sha2_512_hash(hash: &mut [u8], input: &[u8]) -> Result<(), String> {
 
 
 /// signature: 64 bytes
-/// secret: secret key, 32 bytes
+/// secret: secret key
 /// msg: message to sign
 /// len: lentgh of the message
-pub fn ed25519_sign(signature: &mut [u8], secret_key: &[u8], message: &[u8]) -> Result<(), String> {
-    if secret_key.len() != KEY_LEN {
-        return Err(String::from("Public key length error"));
-    }
+pub fn ed25519_sign(signature: &mut [u8], secret_key: &SecretKey, message: &[u8]) -> Result<(), String> {
     if signature.len() != SIGN_LEN {
         return Err(String::from("Signature length error"));
     }
@@ -104,7 +164,7 @@ pub fn ed25519_sign(signature: &mut [u8], secret_key: &[u8], message: &[u8]) ->
 
     unsafe {
         Ed25519_sign(signature.as_ptr(),
-                     secret_key.as_ptr(),
+                     secret_key.as_bytes().as_ptr(),
                      message.as_ptr(),
                      mlen);
     }
@@ -115,27 +175,243 @@ pub fn ed25519_sign(signature: &mut [u8], secret_key: &[u8], message: &[u8]) ->
 
 
 
+/// public_key: public key matching the secret used to sign, 32 bytes
+/// message: message that was signed
+/// signature: signature to check, 64 bytes
+pub fn ed25519_verify(public_key: &[u8], message: &[u8], signature: &[u8]) -> Result<bool, String> {
+    if public_key.len() != KEY_LEN {
+        return Err(String::from("Public key length error"));
+    }
+    if signature.len() != SIGN_LEN {
+        return Err(String::from("Signature length error"));
+    }
+
+    if message.is_empty() {
+        return Err(String::from("Can't use an empty message"));
+    }
+
+    let mlen = message.len() as u32;
+
+    let valid = unsafe {
+        Ed25519_verify(public_key.as_ptr(), message.as_ptr(), mlen, signature.as_ptr())
+    };
+
+    return Ok(valid);
+}
+
+
+
+/// public_key: derived public key, 32 bytes
+/// secret_key: secret key
+pub fn ed25519_secret_to_public(public_key: &mut [u8], secret_key: &SecretKey) -> Result<(), String> {
+    if public_key.len() != KEY_LEN {
+        return Err(String::from("Public key length error"));
+    }
+
+    unsafe {
+        Ed25519_secret_to_public(public_key.as_ptr(), secret_key.as_bytes().as_ptr());
+    }
+
+    return Ok(());
+}
+
+
+
+// id-ed25519 OID (1.3.101.112), DER-encoded
+const ED25519_OID: [u8; 3] = [0x2b, 0x65, 0x70];
+
+/// DER length encoding per X.690: short form for < 0x80, long form otherwise.
+fn der_encode_len(len: usize) -> Vec<u8> {
+    if len < 0x80 {
+        return vec![len as u8];
+    }
+
+    let mut bytes: Vec<u8> = Vec::new();
+    let mut remaining = len;
+    while remaining > 0 {
+        bytes.insert(0, (remaining & 0xff) as u8);
+        remaining >>= 8;
+    }
+
+    let mut out = vec![0x80 | bytes.len() as u8];
+    out.extend(bytes);
+    return out;
+}
+
+/// Reads a DER length at `data[pos]`, returning (length, bytes consumed).
+fn der_decode_len(data: &[u8], pos: usize) -> Result<(usize, usize), String> {
+    if pos >= data.len() {
+        return Err(String::from("Truncated DER length"));
+    }
+
+    let first = data[pos];
+    let (len, consumed) = if first & 0x80 == 0 {
+        (first as usize, 1)
+    } else {
+        let nbytes = (first & 0x7f) as usize;
+        let content_start = pos.checked_add(1).ok_or_else(|| String::from("Truncated DER length"))?;
+        let content_end = content_start.checked_add(nbytes).ok_or_else(|| String::from("Truncated DER length"))?;
+        if nbytes == 0 || content_end > data.len() {
+            return Err(String::from("Truncated DER length"));
+        }
+        if nbytes > std::mem::size_of::<usize>() {
+            return Err(String::from("DER length too large"));
+        }
+
+        let mut len: usize = 0;
+        for &b in &data[content_start..content_end] {
+            len = len << 8 | b as usize;
+        }
+
+        (len, 1 + nbytes)
+    };
+
+    let remaining = data.len() - pos.checked_add(consumed).ok_or_else(|| String::from("Truncated DER length"))?;
+    if len > remaining {
+        return Err(String::from("DER length exceeds available data"));
+    }
+
+    return Ok((len, consumed));
+}
+
+/// Wraps `raw` (an Ed25519 public key or signature) in the SEQUENCE { SEQUENCE { OID }, BIT STRING }
+/// SubjectPublicKeyInfo form. For a public key this is the standard encoding that OpenSSL and other
+/// DER consumers read; for a signature it is only a container (see `ed25519_signature_to_der`).
+fn ed25519_der_encode(raw: &[u8]) -> Vec<u8> {
+    let mut alg_id = vec![0x06, ED25519_OID.len() as u8];
+    alg_id.extend_from_slice(&ED25519_OID);
+
+    let mut alg_seq = vec![0x30];
+    alg_seq.extend(der_encode_len(alg_id.len()));
+    alg_seq.extend(alg_id);
+
+    let mut bit_string = vec![0x03];
+    bit_string.extend(der_encode_len(raw.len() + 1));
+    bit_string.push(0x00); // no unused bits
+    bit_string.extend_from_slice(raw);
+
+    let mut content = alg_seq;
+    content.extend(bit_string);
+
+    let mut out = vec![0x30];
+    out.extend(der_encode_len(content.len()));
+    out.extend(content);
+
+    return out;
+}
+
+/// Unwraps the SEQUENCE { SEQUENCE { OID }, BIT STRING } form produced by `ed25519_der_encode`,
+/// validating every tag/length and rejecting trailing bytes.
+fn ed25519_der_decode(der: &[u8], expected_len: usize) -> Result<Vec<u8>, String> {
+    let mut pos = 0;
+
+    if der.get(pos) != Some(&0x30) {
+        return Err(String::from("Expected outer SEQUENCE tag"));
+    }
+    pos += 1;
+    let (seq_len, consumed) = der_decode_len(der, pos)?;
+    pos += consumed;
+    if pos + seq_len != der.len() {
+        return Err(String::from("Trailing bytes after outer SEQUENCE"));
+    }
+
+    if der.get(pos) != Some(&0x30) {
+        return Err(String::from("Expected algorithm-identifier SEQUENCE tag"));
+    }
+    pos += 1;
+    let (alg_len, consumed) = der_decode_len(der, pos)?;
+    pos += consumed;
+    let alg_content_start = pos;
+
+    if der.get(pos) != Some(&0x06) {
+        return Err(String::from("Expected OID tag"));
+    }
+    pos += 1;
+    let (oid_len, consumed) = der_decode_len(der, pos)?;
+    pos += consumed;
+    if oid_len != ED25519_OID.len() || der[pos..pos + oid_len] != ED25519_OID[..] {
+        return Err(String::from("Unexpected algorithm OID"));
+    }
+    pos += oid_len;
+    if pos - alg_content_start != alg_len {
+        return Err(String::from("Algorithm-identifier SEQUENCE length mismatch"));
+    }
+
+    if der.get(pos) != Some(&0x03) {
+        return Err(String::from("Expected BIT STRING tag"));
+    }
+    pos += 1;
+    let (bits_len, consumed) = der_decode_len(der, pos)?;
+    pos += consumed;
+    if bits_len == 0 {
+        return Err(String::from("Empty BIT STRING"));
+    }
+    if der.get(pos) != Some(&0x00) {
+        return Err(String::from("Unsupported BIT STRING padding"));
+    }
+    pos += 1;
+
+    let raw_len = bits_len - 1;
+    if raw_len != expected_len {
+        return Err(String::from("Unexpected key/signature length"));
+    }
+    if pos + raw_len != der.len() {
+        return Err(String::from("Trailing bytes after BIT STRING"));
+    }
+
+    return Ok(der[pos..pos + raw_len].to_vec());
+}
+
+/// Encodes a 32-byte Ed25519 public key as a DER SubjectPublicKeyInfo-style blob.
+pub fn ed25519_public_key_to_der(public_key: &[u8]) -> Result<Vec<u8>, String> {
+    if public_key.len() != KEY_LEN {
+        return Err(String::from("Public key length error"));
+    }
+
+    return Ok(ed25519_der_encode(public_key));
+}
+
+/// Decodes a DER blob produced by `ed25519_public_key_to_der` back into a 32-byte public key.
+pub fn ed25519_public_key_from_der(der: &[u8]) -> Result<Vec<u8>, String> {
+    return ed25519_der_decode(der, KEY_LEN);
+}
+
+/// Encodes a 64-byte Ed25519 signature using the same DER tag-length-value form as the public key.
+/// This reuses `ed25519_der_encode`'s SPKI-shaped `SEQUENCE { SEQUENCE { OID }, BIT STRING }` framing
+/// purely as a convenient, round-trippable container for a raw signature; it is not a standard
+/// X.509/PKCS DER construct and won't interoperate with other DER consumers.
+pub fn ed25519_signature_to_der(signature: &[u8]) -> Result<Vec<u8>, String> {
+    if signature.len() != SIGN_LEN {
+        return Err(String::from("Signature length error"));
+    }
+
+    return Ok(ed25519_der_encode(signature));
+}
+
+/// Decodes a DER blob produced by `ed25519_signature_to_der` back into a 64-byte signature.
+pub fn ed25519_signature_from_der(der: &[u8]) -> Result<Vec<u8>, String> {
+    return ed25519_der_decode(der, SIGN_LEN);
+}
+
+
+
 /// mypublic: generated public key, 32 bytes
-/// secret: secret key, 32 bytes
+/// secret: secret key
 /// basepoint: initial point, 32 bytes, default is 9
 pub fn curve25519_crypto_scalarmult(public_key: &mut [u8],
-                                    secret_key: &[u8],
+                                    secret_key: &SecretKey,
                                     basepoint: &[u8])
                                     -> Result<(), String> {
     if public_key.len() != KEY_LEN {
         return Err(String::from("Public key length error"));
     }
 
-    if secret_key.len() != KEY_LEN {
-        return Err(String::from("Secret key length error"));
-    }
-
     if basepoint.len() != KEY_LEN {
         return Err(String::from("Basepoint length error"));
     }
 
     unsafe {
-        Curve25519_crypto_scalarmult(public_key.as_ptr(), secret_key.as_ptr(), basepoint.as_ptr());
+        Curve25519_crypto_scalarmult(public_key.as_ptr(), secret_key.as_bytes().as_ptr(), basepoint.as_ptr());
     }
 
     return Ok(());
@@ -168,10 +444,6 @@ pub fn chacha20poly1305_aead_decrypt(message: &mut [u8],
         return Err(String::from("Nonce length error"));
     }
 
-    if message.is_empty() {
-        return Err(String::from("Can't use an empty message"));
-    }
-
     if ciphertext.len() != message.len() {
         return Err(String::from("Message and ciphertext have different lengths"));
     }
@@ -185,10 +457,21 @@ pub fn chacha20poly1305_aead_decrypt(message: &mut [u8],
         aad.as_ptr()
     };
 
+    let mptr = if message.is_empty() {
+        ptr::null()
+    } else {
+        message.as_ptr()
+    };
+
+    let cptr = if ciphertext.is_empty() {
+        ptr::null()
+    } else {
+        ciphertext.as_ptr()
+    };
 
     let val = unsafe {
-        Chacha20Poly1305_aead_decrypt(message.as_ptr(),
-                                      ciphertext.as_ptr(),
+        Chacha20Poly1305_aead_decrypt(mptr,
+                                      cptr,
                                       mlen,
                                       mac.as_ptr(),
                                       aadptr,
@@ -230,10 +513,6 @@ pub fn chacha20poly1305_aead_encrypt(ciphertext: &mut [u8],
         return Err(String::from("Nonce length error"));
     }
 
-    if message.is_empty() {
-        return Err(String::from("Can't use an empty message"));
-    }
-
     if ciphertext.len() != message.len() {
         return Err(String::from("Message and ciphertext have different lengths"));
     }
@@ -247,11 +526,22 @@ pub fn chacha20poly1305_aead_encrypt(ciphertext: &mut [u8],
         aad.as_ptr()
     };
 
+    let mptr = if message.is_empty() {
+        ptr::null()
+    } else {
+        message.as_ptr()
+    };
+
+    let cptr = if ciphertext.is_empty() {
+        ptr::null()
+    } else {
+        ciphertext.as_ptr()
+    };
 
     let val = unsafe {
-        Chacha20Poly1305_aead_encrypt(ciphertext.as_ptr(),
+        Chacha20Poly1305_aead_encrypt(cptr,
                                       mac.as_ptr(),
-                                      message.as_ptr(),
+                                      mptr,
                                       mlen,
                                       aadptr,
                                       aadlen,
@@ -267,6 +557,101 @@ pub fn chacha20poly1305_aead_encrypt(ciphertext: &mut [u8],
 
 
 
+/// A combined-mode AEAD: encryption/decryption in place with a detached tag, plus
+/// `seal`/`open` helpers for callers that prefer ciphertext||tag as a single buffer.
+pub trait Aead {
+    /// Encrypts `buffer` in place and returns the 16-byte authentication tag.
+    fn encrypt_in_place_detached(&self,
+                                 nonce: &[u8],
+                                 aad: &[u8],
+                                 buffer: &mut [u8],
+                                 key: &[u8])
+                                 -> Result<[u8; MAC_LEN], String>;
+
+    /// Decrypts `buffer` in place, checking it against `tag`.
+    fn decrypt_in_place_detached(&self,
+                                 nonce: &[u8],
+                                 aad: &[u8],
+                                 buffer: &mut [u8],
+                                 tag: &[u8],
+                                 key: &[u8])
+                                 -> Result<(), String>;
+
+    /// Encrypts `plaintext` and returns `ciphertext || tag`.
+    fn seal(&self, nonce: &[u8], aad: &[u8], plaintext: &[u8], key: &[u8]) -> Vec<u8> {
+        let mut buffer = plaintext.to_vec();
+        let tag = self.encrypt_in_place_detached(nonce, aad, &mut buffer, key)
+            .expect("seal: encryption failed");
+        buffer.extend_from_slice(&tag);
+        return buffer;
+    }
+
+    /// Splits `ciphertext_and_tag` into ciphertext and tag, and decrypts it.
+    fn open(&self,
+           nonce: &[u8],
+           aad: &[u8],
+           ciphertext_and_tag: &[u8],
+           key: &[u8])
+           -> Result<Vec<u8>, String> {
+        if ciphertext_and_tag.len() < MAC_LEN {
+            return Err(String::from("Ciphertext shorter than the authentication tag"));
+        }
+
+        let split = ciphertext_and_tag.len() - MAC_LEN;
+        let mut buffer = ciphertext_and_tag[..split].to_vec();
+        let tag = &ciphertext_and_tag[split..];
+
+        self.decrypt_in_place_detached(nonce, aad, &mut buffer, tag, key)?;
+
+        return Ok(buffer);
+    }
+}
+
+/// ChaCha20-Poly1305 AEAD (RFC 8439) backed by the `Chacha20Poly1305_aead_*` FFI calls.
+pub struct ChaCha20Poly1305;
+
+impl Aead for ChaCha20Poly1305 {
+    fn encrypt_in_place_detached(&self,
+                                 nonce: &[u8],
+                                 aad: &[u8],
+                                 buffer: &mut [u8],
+                                 key: &[u8])
+                                 -> Result<[u8; MAC_LEN], String> {
+        let plaintext = buffer.to_vec();
+        let mut mac = [0u8; MAC_LEN];
+        let mut ciphertext = vec![0u8; plaintext.len()];
+
+        let success = chacha20poly1305_aead_encrypt(&mut ciphertext, &mut mac, &plaintext, aad, key, nonce)?;
+        if !success {
+            return Err(String::from("Encryption failed"));
+        }
+
+        buffer.copy_from_slice(&ciphertext);
+        return Ok(mac);
+    }
+
+    fn decrypt_in_place_detached(&self,
+                                 nonce: &[u8],
+                                 aad: &[u8],
+                                 buffer: &mut [u8],
+                                 tag: &[u8],
+                                 key: &[u8])
+                                 -> Result<(), String> {
+        let ciphertext = buffer.to_vec();
+        let mut plaintext = vec![0u8; ciphertext.len()];
+
+        let valid = chacha20poly1305_aead_decrypt(&mut plaintext, tag, &ciphertext, aad, key, nonce)?;
+        if !valid {
+            return Err(String::from("Authentication tag mismatch"));
+        }
+
+        buffer.copy_from_slice(&plaintext);
+        return Ok(());
+    }
+}
+
+
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -334,13 +719,84 @@ mod tests {
         assert_eq!(plaintext, PLAINTEXT);
     }
 
+    #[test]
+    fn test_aead_encrypt_in_place_detached_matches_mac() {
+        let aead = ChaCha20Poly1305;
+        let mut buffer = PLAINTEXT.to_vec();
+
+        let tag = aead.encrypt_in_place_detached(&NONCE, &[], &mut buffer, &KEY)
+            .expect("encrypt_in_place_detached");
+
+        assert_eq!(buffer, CIPHERTEXT);
+        assert_eq!(&tag, &MAC);
+    }
+
+    #[test]
+    fn test_aead_decrypt_in_place_detached_roundtrip() {
+        let aead = ChaCha20Poly1305;
+        let mut buffer = CIPHERTEXT.to_vec();
+
+        aead.decrypt_in_place_detached(&NONCE, &[], &mut buffer, &MAC, &KEY)
+            .expect("decrypt_in_place_detached");
+
+        assert_eq!(buffer, PLAINTEXT);
+    }
+
+    #[test]
+    fn test_aead_decrypt_in_place_detached_rejects_bad_tag() {
+        let aead = ChaCha20Poly1305;
+        let mut buffer = CIPHERTEXT.to_vec();
+        let mut bad_mac = MAC;
+        bad_mac[0] ^= 0xff;
+
+        assert!(aead.decrypt_in_place_detached(&NONCE, &[], &mut buffer, &bad_mac, &KEY).is_err());
+    }
+
+    #[test]
+    fn test_aead_seal_open_roundtrip() {
+        let aead = ChaCha20Poly1305;
+
+        let sealed = aead.seal(&NONCE, &[], &PLAINTEXT, &KEY);
+        assert_eq!(sealed.len(), PLAINTEXT.len() + MAC_LEN);
+
+        let opened = aead.open(&NONCE, &[], &sealed, &KEY).expect("open");
+        assert_eq!(opened, PLAINTEXT);
+    }
+
+    #[test]
+    fn test_aead_seal_open_roundtrip_empty_message() {
+        let aead = ChaCha20Poly1305;
+
+        let sealed = aead.seal(&NONCE, b"aad", &[], &KEY);
+        assert_eq!(sealed.len(), MAC_LEN);
+
+        let opened = aead.open(&NONCE, b"aad", &sealed, &KEY).expect("open");
+        assert!(opened.is_empty());
+    }
+
+    #[test]
+    fn test_secret_key_length_validation() {
+        assert!(SecretKey::new(&KEY).is_ok());
+        assert!(SecretKey::new(&KEY[..31]).is_err());
+    }
+
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq(&KEY, &KEY));
+        let mut tampered = KEY;
+        tampered[0] ^= 0xff;
+        assert!(!constant_time_eq(&KEY, &tampered));
+        assert!(!constant_time_eq(&KEY, &KEY[..31]));
+    }
+
     #[test]
     fn test_curve25519_scalar_mult() {
         let mut basepoint: [u8; 32] = [0; 32];
         basepoint[0] = 9;
 
+        let secret_key = SecretKey::new(&KEY).unwrap();
         let mut public_key: [u8; 32] = [0; 32];
-        assert_eq!(curve25519_crypto_scalarmult(&mut public_key, &KEY, &basepoint),
+        assert_eq!(curve25519_crypto_scalarmult(&mut public_key, &secret_key, &basepoint),
                    Ok(()));
 
         print_array("Public key", &public_key);
@@ -355,7 +811,8 @@ mod tests {
                            0x66, 0x91, 0x39, 0xce, 0xe0, 0xd6, 0x85, 0x9e, 0x48, 0xa3, 0xed, 0x3b,
                            0x5b, 0x7c, 0x89, 0xc1, 0x5a, 0x49, 0xf3, 0x7];
 
-        assert_eq!(ed25519_sign(signature.as_mut_slice(), &KEY, &message),
+        let secret_key = SecretKey::new(&KEY).unwrap();
+        assert_eq!(ed25519_sign(signature.as_mut_slice(), &secret_key, &message),
                    Ok(()));
     }
 
@@ -367,80 +824,64 @@ mod tests {
     }
 
     #[test]
-    fn test_gec() {
-        // common basepoint
-        let mut basepoint: [u8; 32] = [0; 32];
-        basepoint[0] = 9;
+    fn test_ed25519_verify_roundtrip() {
+        let secret_key = SecretKey::new(&KEY).unwrap();
+        let mut public_key = vec![0; 32];
+        assert_eq!(ed25519_secret_to_public(public_key.as_mut_slice(), &secret_key), Ok(()));
+
+        let message = vec![0x6c, 0xe8, 0xaa, 0x8e, 0xed, 0x97, 0x50, 0xb5, 0xb8, 0x74, 0xf7, 0x29,
+                           0x66, 0x91, 0x39, 0xce, 0xe0, 0xd6, 0x85, 0x9e, 0x48, 0xa3, 0xed, 0x3b,
+                           0x5b, 0x7c, 0x89, 0xc1, 0x5a, 0x49, 0xf3, 0x7];
+
+        let mut signature = vec![0; 64];
+        assert_eq!(ed25519_sign(signature.as_mut_slice(), &secret_key, &message), Ok(()));
 
-        // A
-        // given secret key
-        let q_ae = vec![0x98, 0x99, 0x22, 0xFA, 0x6E, 0x87, 0x2B, 0xC1, 0x45, 0x84, 0x80, 0xAA,
-                        0xF8, 0x65, 0xA5, 0xBA, 0xB8, 0x61, 0x85, 0x77, 0xC2, 0xEC, 0x37, 0xF9,
-                        0xAF, 0xB3, 0xAE, 0x47, 0x83, 0x2C, 0xA4, 0x44];
+        assert_eq!(ed25519_verify(&public_key, &message, &signature), Ok(true));
 
-        // given public key
-        let p_ae = vec![0x98, 0x99, 0x22, 0xFA, 0x6E, 0x87, 0x2B, 0xC1, 0x45, 0x84, 0x80, 0xAA,
-                        0xF8, 0x65, 0xA5, 0xBA, 0xB8, 0x61, 0x85, 0x77, 0xC2, 0xEC, 0x37, 0xF9,
-                        0xAF, 0xB3, 0xAE, 0x47, 0x83, 0x2C, 0xA4, 0x44];
+        let mut tampered = message.clone();
+        tampered[0] ^= 0xff;
+        assert_eq!(ed25519_verify(&public_key, &tampered, &signature), Ok(false));
+    }
 
-        // 1. A generates an ephemeral (random) curve25519 key pair (Pae, Qae) and sends Pae.
+    #[test]
+    fn test_ed25519_public_key_der_roundtrip() {
+        let secret_key = SecretKey::new(&KEY).unwrap();
+        let mut public_key = vec![0; 32];
+        assert_eq!(ed25519_secret_to_public(public_key.as_mut_slice(), &secret_key), Ok(()));
 
-        // B
-        // given secrect key
-        let q_be = vec![0xE4, 0xD5, 0x17, 0x13, 0xEB, 0xF8, 0x82, 0xCC, 0x7A, 0x90, 0x29, 0x14,
-                        0x59, 0xCC, 0x84, 0x7E, 0xA2, 0xD3, 0xE9, 0x5E, 0x9E, 0x4, 0x26, 0x90,
-                        0x83, 0x44, 0xE9, 0x5B, 0xA, 0xB7, 0x14, 0x42];
+        let der = ed25519_public_key_to_der(&public_key).expect("encode");
+        assert_eq!(der[0], 0x30);
 
-        // given public key
-        let p_be = vec![0x13, 0x4B, 0x63, 0x9E, 0x68, 0x0, 0x9C, 0x72, 0x8D, 0xB3, 0x64, 0xA0,
-                        0xCD, 0xA3, 0xF3, 0x2F, 0xB5, 0x4D, 0x23, 0x8, 0x7F, 0x33, 0x2C, 0x79,
-                        0x9F, 0xCD, 0x5F, 0x7D, 0x49, 0xA8, 0x25, 0xB5];
+        let decoded = ed25519_public_key_from_der(&der).expect("decode");
+        assert_eq!(decoded, public_key);
 
-        // 2. B generates ephemeral curve25519 key pair (Pbe, Qbe).
+        let mut trailing = der.clone();
+        trailing.push(0x00);
+        assert!(ed25519_public_key_from_der(&trailing).is_err());
+    }
 
-        // 3. B computes the shared secret: z = scalar_multiplication(Qbe, Pae)
-        let mut z = vec![0; 32];
-        assert_eq!(curve25519_crypto_scalarmult(z.as_mut_slice(), q_be.as_slice(), &p_be.as_slice()),
-                   Ok(()));
-        print_array("z", &z);
-
-		// 4. B uses the key derivation function kdf(z,1) to compute Kb || Sb, kdf(z,0) to
-		// compute Ka || Sa, and kdf(z,2) to compute Kclient || Sclient.
-		// kdf(z,partyIdent) = SHA512( 0 || z || partyIdent)
-		// (0 for A, 1 for B and 2 for key material returned to the callee)
-		
-		// kdf(z,0) to compute Ka || Sa
-		let mut ka_sa = vec![0;64];
-		let mut input = z.clone();
-		input.push(0); 
-		assert_eq!(sha2_512_hash(ka_sa.as_mut_slice(), input.as_slice()), Ok(()));
-		print_array("k_a", &ka_sa[0..32]);
-		print_array("s_a", &ka_sa[32..64]);
-		
-		// kdf(z,1) to compute Kb || Sb
-		let mut kb_sb = vec![0;64];
-		let mut input = z.clone();
-		input.push(1); 
-		assert_eq!(sha2_512_hash(kb_sb.as_mut_slice(), input.as_slice()), Ok(()));
-		print_array("k_b", &kb_sb[0..32]);
-		print_array("s_b", &kb_sb[32..64]);
-		
-		// kdf(z,2) to compute Kclient || Sclient
-		let mut kc_sc = vec![0;64];
-		let mut input = z.clone();
-		input.push(2); 
-		assert_eq!(sha2_512_hash(kc_sc.as_mut_slice(), input.as_slice()), Ok(()));
-		print_array("k_c", &kc_sc[0..32]);
-		print_array("s_c", &kc_sc[32..64]);
-		
-		// 5. B computes the ed25519 signature: sig = signQb(Pbe || Pae)
-		let mut sig = vec![0;64];
-		let mut pbe_pae = p_be.clone();
-		pbe_pae.append(&mut p_ae.clone());
-		assert_eq!(ed25519_sign(sig.as_mut_slice(), q_be.as_slice(), pbe_pae.as_slice()), Ok(()));
-		print_array("sig", &sig);
-    }
-    
-    
-    
+    #[test]
+    fn test_ed25519_public_key_from_der_rejects_overflowing_length() {
+        let mut malformed = vec![0x30, 0x80 | 127];
+        malformed.extend(vec![0xff; 127]);
+        assert!(ed25519_public_key_from_der(&malformed).is_err());
+    }
+
+    #[test]
+    fn test_ed25519_public_key_from_der_rejects_truncated_oid() {
+        let malformed: Vec<u8> = vec![0x30, 0x05, 0x30, 0x03, 0x06, 0x03, 0xAA];
+        assert!(ed25519_public_key_from_der(&malformed).is_err());
+    }
+
+    #[test]
+    fn test_ed25519_signature_der_roundtrip() {
+        let message = vec![1, 2, 3];
+        let secret_key = SecretKey::new(&KEY).unwrap();
+        let mut signature = vec![0; 64];
+        assert_eq!(ed25519_sign(signature.as_mut_slice(), &secret_key, &message), Ok(()));
+
+        let der = ed25519_signature_to_der(&signature).expect("encode");
+        let decoded = ed25519_signature_from_der(&der).expect("decode");
+        assert_eq!(decoded, signature);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,215 @@
+/// Primitive resolver for the Noise Protocol Framework: `Dh`, `Cipher`, and `Hash` traits
+/// so a `snow`-style resolver can pick HACL* as the backend for 25519/ChaChaPoly/SHA512
+/// Noise patterns.
+
+use crate::kdf;
+use crate::{Aead, ChaCha20Poly1305, SecretKey};
+
+/// Clamps `random_bytes` into a valid Curve25519 secret key per RFC 7748: clear bits 0,1,2
+/// of byte 0, clear bit 7 and set bit 6 of byte 31.
+pub(crate) fn curve25519_clamp(random_bytes: &mut [u8; 32]) {
+    random_bytes[0] &= 0xf8;
+    random_bytes[31] &= 0x7f;
+    random_bytes[31] |= 0x40;
+}
+
+/// Diffie-Hellman key agreement over Curve25519.
+pub trait Dh {
+    /// Clamps `random_bytes` into a valid Curve25519 secret key and derives the matching
+    /// public key: clear bits 0,1,2 of byte 0, clear bit 7 and set bit 6 of byte 31.
+    fn generate_keypair(&self, random_bytes: [u8; 32]) -> Result<(SecretKey, [u8; 32]), String>;
+
+    /// Computes the shared secret between `secret` and `public`.
+    fn dh(&self, secret: &SecretKey, public: &[u8]) -> Result<[u8; 32], String>;
+}
+
+pub struct Curve25519Dh;
+
+impl Dh for Curve25519Dh {
+    fn generate_keypair(&self, mut random_bytes: [u8; 32]) -> Result<(SecretKey, [u8; 32]), String> {
+        curve25519_clamp(&mut random_bytes);
+
+        let secret = SecretKey::new(&random_bytes)?;
+
+        let mut basepoint = [0u8; 32];
+        basepoint[0] = 9;
+
+        let mut public = [0u8; 32];
+        crate::curve25519_crypto_scalarmult(&mut public, &secret, &basepoint)?;
+
+        return Ok((secret, public));
+    }
+
+    fn dh(&self, secret: &SecretKey, public: &[u8]) -> Result<[u8; 32], String> {
+        let mut shared = [0u8; 32];
+        crate::curve25519_crypto_scalarmult(&mut shared, secret, public)?;
+        return Ok(shared);
+    }
+}
+
+/// AEAD cipher keyed by an 8-byte little-endian counter, per the Noise spec's nonce format.
+pub trait Cipher {
+    fn encrypt(&self, key: &[u8], nonce: u64, aad: &[u8], plaintext: &[u8]) -> Vec<u8>;
+    fn decrypt(&self, key: &[u8], nonce: u64, aad: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, String>;
+}
+
+/// Builds the 12-byte ChaCha20-Poly1305 nonce Noise expects: 4 zero bytes followed by an
+/// 8-byte little-endian counter.
+fn noise_nonce(counter: u64) -> [u8; 12] {
+    let mut nonce = [0u8; 12];
+    nonce[4..12].copy_from_slice(&counter.to_le_bytes());
+    return nonce;
+}
+
+impl Cipher for ChaCha20Poly1305 {
+    fn encrypt(&self, key: &[u8], nonce: u64, aad: &[u8], plaintext: &[u8]) -> Vec<u8> {
+        return self.seal(&noise_nonce(nonce), aad, plaintext, key);
+    }
+
+    fn decrypt(&self, key: &[u8], nonce: u64, aad: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, String> {
+        return self.open(&noise_nonce(nonce), aad, ciphertext, key);
+    }
+}
+
+/// Hash function backing Noise's `MixHash`/`MixKey` operations.
+pub trait Hash {
+    const BLOCK_LEN: usize;
+    const HASHLEN: usize;
+
+    fn hash(&self, data: &[u8]) -> Vec<u8>;
+
+    /// HMAC-based HKDF used by `MixKey`: derives `num_outputs` chaining-key-sized outputs
+    /// (1 to 3, per the Noise spec) from `chaining_key` and `input_key_material`.
+    fn hkdf(&self,
+           chaining_key: &[u8],
+           input_key_material: &[u8],
+           num_outputs: usize)
+           -> Result<Vec<Vec<u8>>, String>;
+}
+
+pub struct Sha512Hash;
+
+impl Hash for Sha512Hash {
+    const BLOCK_LEN: usize = 128;
+    const HASHLEN: usize = 64;
+
+    fn hash(&self, data: &[u8]) -> Vec<u8> {
+        let mut out = vec![0u8; Self::HASHLEN];
+        crate::sha2_512_hash(&mut out, data).expect("sha2_512_hash");
+        return out;
+    }
+
+    fn hkdf(&self,
+           chaining_key: &[u8],
+           input_key_material: &[u8],
+           num_outputs: usize)
+           -> Result<Vec<Vec<u8>>, String> {
+        if !(1..=3).contains(&num_outputs) {
+            return Err(String::from("hkdf supports 1 to 3 outputs"));
+        }
+
+        let prk = kdf::hkdf_extract(chaining_key, input_key_material);
+        let okm = kdf::hkdf_expand(&prk, &[], num_outputs * Self::HASHLEN)?;
+
+        let mut outputs = Vec::with_capacity(num_outputs);
+        for i in 0..num_outputs {
+            outputs.push(okm[i * Self::HASHLEN..(i + 1) * Self::HASHLEN].to_vec());
+        }
+
+        return Ok(outputs);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_curve25519_dh_clamping() {
+        let dh = Curve25519Dh;
+        let (secret, _public) = dh.generate_keypair([0xff; 32]).expect("generate_keypair");
+
+        let bytes = secret.as_bytes();
+        assert_eq!(bytes[0] & 0x07, 0);
+        assert_eq!(bytes[31] & 0x80, 0);
+        assert_eq!(bytes[31] & 0x40, 0x40);
+    }
+
+    #[test]
+    fn test_curve25519_dh_shared_secret_agrees() {
+        let dh = Curve25519Dh;
+        let (secret_a, public_a) = dh.generate_keypair([0x11; 32]).expect("generate_keypair");
+        let (secret_b, public_b) = dh.generate_keypair([0x22; 32]).expect("generate_keypair");
+
+        let shared_ab = dh.dh(&secret_a, &public_b).expect("dh");
+        let shared_ba = dh.dh(&secret_b, &public_a).expect("dh");
+
+        assert_eq!(shared_ab, shared_ba);
+    }
+
+    #[test]
+    fn test_cipher_encrypt_decrypt_roundtrip() {
+        let cipher = ChaCha20Poly1305;
+        let key = [0x07u8; 32];
+        let aad = b"noise handshake";
+        let plaintext = b"transport message";
+
+        let ciphertext = cipher.encrypt(&key, 42, aad, plaintext);
+        let decrypted = cipher.decrypt(&key, 42, aad, &ciphertext).expect("decrypt");
+
+        assert_eq!(decrypted, plaintext);
+    }
+
+    #[test]
+    fn test_cipher_encrypt_decrypt_roundtrip_empty_payload() {
+        let cipher = ChaCha20Poly1305;
+        let key = [0x07u8; 32];
+        let aad = b"noise handshake";
+
+        let ciphertext = cipher.encrypt(&key, 1, aad, &[]);
+        let decrypted = cipher.decrypt(&key, 1, aad, &ciphertext).expect("decrypt");
+
+        assert!(decrypted.is_empty());
+    }
+
+    #[test]
+    fn test_cipher_decrypt_rejects_wrong_nonce() {
+        let cipher = ChaCha20Poly1305;
+        let key = [0x07u8; 32];
+        let ciphertext = cipher.encrypt(&key, 1, &[], b"message");
+
+        assert!(cipher.decrypt(&key, 2, &[], &ciphertext).is_err());
+    }
+
+    #[test]
+    fn test_sha512_hash_len_and_constants() {
+        let hash = Sha512Hash;
+        assert_eq!(Sha512Hash::HASHLEN, 64);
+        assert_eq!(Sha512Hash::BLOCK_LEN, 128);
+        assert_eq!(hash.hash(b"message").len(), 64);
+    }
+
+    #[test]
+    fn test_sha512_hash_accepts_empty_input() {
+        let hash = Sha512Hash;
+        assert_eq!(hash.hash(&[]).len(), 64);
+    }
+
+    #[test]
+    fn test_sha512_hkdf_output_count_and_length() {
+        let hash = Sha512Hash;
+        let outputs = hash.hkdf(&[0u8; 64], b"ikm", 3).expect("hkdf");
+
+        assert_eq!(outputs.len(), 3);
+        for output in &outputs {
+            assert_eq!(output.len(), 64);
+        }
+    }
+
+    #[test]
+    fn test_sha512_hkdf_rejects_bad_output_count() {
+        let hash = Sha512Hash;
+        assert!(hash.hkdf(&[0u8; 64], b"ikm", 0).is_err());
+        assert!(hash.hkdf(&[0u8; 64], b"ikm", 4).is_err());
+    }
+}